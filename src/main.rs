@@ -5,6 +5,9 @@ use std::time::{Duration, Instant};
 use rand::distributions::Uniform;
 use rand::Rng;
 
+use serde::Deserialize;
+
+use sdl2::controller::{Axis, Button, GameController};
 use sdl2::event::{Event, WindowEvent};
 use sdl2::gfx::primitives::DrawRenderer;
 use sdl2::keyboard::Keycode;
@@ -33,6 +36,123 @@ fn f64_duration(duration: &Duration) -> f64 {
     (duration.as_secs() as f64) + (duration.subsec_nanos() as f64) / (NANOS_PER_SEC as f64)
 }
 
+// Tuning constants that used to be scattered `const`s on `Ship`/`Asteroid`,
+// now loaded from a TOML file so players and testers can retune handling
+// and difficulty without recompiling. Any field (or the whole file) may be
+// left out; missing values fall back to the defaults below.
+const CONFIG_PATH: &str = "asteroids.toml";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+struct Config {
+    window: WindowConfig,
+    ship: ShipConfig,
+    asteroid: AsteroidConfig,
+    bullet: BulletConfig,
+}
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            window: WindowConfig::default(),
+            ship: ShipConfig::default(),
+            asteroid: AsteroidConfig::default(),
+            bullet: BulletConfig::default(),
+        }
+    }
+}
+impl Config {
+    fn load() -> Self {
+        let mut config: Config = std::fs::read_to_string(CONFIG_PATH)
+            .ok()
+            .and_then(|contents| toml::from_str(&contents).ok())
+            .unwrap_or_default();
+        config.asteroid.sanitize();
+        config
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+struct WindowConfig {
+    width: f64,
+    height: f64,
+}
+impl Default for WindowConfig {
+    fn default() -> Self {
+        WindowConfig {
+            width: 800.0,
+            height: 600.0,
+        }
+    }
+}
+impl WindowConfig {
+    fn bounds(&self) -> V2 {
+        V2(self.width, self.height)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+struct ShipConfig {
+    // pixels per second per second.
+    accel: f64,
+    // radians per second.
+    angular_accel: f64,
+}
+impl Default for ShipConfig {
+    fn default() -> Self {
+        ShipConfig {
+            accel: 100.0,
+            angular_accel: 4.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+struct AsteroidConfig {
+    initial_radius: f64,
+    min_radius: f64,
+    velocity_change: f64,
+}
+impl Default for AsteroidConfig {
+    fn default() -> Self {
+        AsteroidConfig {
+            initial_radius: 32.0,
+            min_radius: 7.0,
+            velocity_change: 5000.0,
+        }
+    }
+}
+impl AsteroidConfig {
+    // `Asteroid::split` builds a `gen_range(-dv, dv)` from this value, which
+    // panics unless `dv` is strictly positive. A config file is free to set
+    // `velocity_change` to zero or negative, so clamp it to a small positive
+    // floor rather than trusting it to stay in range.
+    fn sanitize(&mut self) {
+        if self.velocity_change <= 0.0 {
+            self.velocity_change = 1.0;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+struct BulletConfig {
+    // pixels per second, added to the ship's own velocity.
+    muzzle_speed: f64,
+    // seconds
+    lifetime: f64,
+}
+impl Default for BulletConfig {
+    fn default() -> Self {
+        BulletConfig {
+            muzzle_speed: 400.0,
+            lifetime: 1.0,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 enum KeyStatus {
     Up,
@@ -64,6 +184,11 @@ struct Keys {
     fire: KeyStatus,
     pause: KeyStatus,
     quit: KeyStatus,
+    // Left stick steering, tracked apart from `left`/`right` so a centred
+    // (or deflected) axis event only ever clears/sets the side the stick
+    // itself drives, instead of stomping whatever the keyboard last wrote.
+    stick_left: bool,
+    stick_right: bool,
 }
 impl Keys {
     fn new() -> Self {
@@ -74,46 +199,89 @@ impl Keys {
             fire: KeyStatus::Up,
             pause: KeyStatus::Up,
             quit: KeyStatus::Up,
+            stick_left: false,
+            stick_right: false,
         }
     }
-    fn with_events(&mut self, event_pump: &mut EventPump) {
-        event_pump.poll_iter().for_each(|event| {
-            let value;
-            match event {
-                Event::KeyDown { repeat: false, .. } => value = KeyStatus::Down,
-                Event::KeyUp { repeat: false, .. } => value = KeyStatus::Up,
-                _ => return,
-            }
+    fn left_down(&self) -> bool {
+        self.left.down() || self.stick_left
+    }
+    fn right_down(&self) -> bool {
+        self.right.down() || self.stick_right
+    }
+    // Left stick X magnitude below which it's treated as centred, so a
+    // controller at rest doesn't register as holding left or right.
+    const STICK_DEADZONE: i16 = 8192;
 
-            match event {
-                Event::KeyDown {
-                    keycode: Some(key),
-                    repeat: false,
-                    ..
+    fn step(&mut self) {
+        self.thrust.step();
+        self.left.step();
+        self.right.step();
+        self.fire.step();
+        self.pause.step();
+        self.quit.step();
+    }
+    fn with_events(&mut self, event_pump: &mut EventPump) {
+        event_pump.poll_iter().for_each(|event| match event {
+            Event::KeyDown {
+                keycode: Some(key),
+                repeat: false,
+                ..
+            } => self.set_key(key, KeyStatus::Down),
+            Event::KeyUp {
+                keycode: Some(key),
+                repeat: false,
+                ..
+            } => self.set_key(key, KeyStatus::Up),
+            Event::ControllerButtonDown { button, .. } => self.set_button(button, KeyStatus::Down),
+            Event::ControllerButtonUp { button, .. } => self.set_button(button, KeyStatus::Up),
+            Event::ControllerAxisMotion {
+                axis: Axis::LeftX,
+                value,
+                ..
+            } => {
+                if value < -Keys::STICK_DEADZONE {
+                    self.stick_left = true;
+                    self.stick_right = false;
+                } else if value > Keys::STICK_DEADZONE {
+                    self.stick_right = true;
+                    self.stick_left = false;
+                } else {
+                    self.stick_left = false;
+                    self.stick_right = false;
                 }
-                | Event::KeyUp {
-                    keycode: Some(key),
-                    repeat: false,
-                    ..
-                } => match key {
-                    Keycode::Up => self.thrust = value,
-                    Keycode::Left => self.left = value,
-                    Keycode::Right => self.right = value,
-                    Keycode::Space => self.fire = value,
-                    Keycode::P => self.pause = value,
-                    Keycode::Q => self.quit = value,
-                    Keycode::Escape => self.quit = value,
-                    _ => (),
-                },
-                Event::Quit { .. } => self.quit = KeyStatus::Down,
-                Event::Window {
-                    win_event: WindowEvent::Close,
-                    ..
-                } => self.quit = KeyStatus::Down,
-                _ => (),
             }
+            Event::Quit { .. } => self.quit = KeyStatus::Down,
+            Event::Window {
+                win_event: WindowEvent::Close,
+                ..
+            } => self.quit = KeyStatus::Down,
+            _ => (),
         })
     }
+    fn set_key(&mut self, key: Keycode, value: KeyStatus) {
+        match key {
+            Keycode::Up => self.thrust = value,
+            Keycode::Left => self.left = value,
+            Keycode::Right => self.right = value,
+            Keycode::Space => self.fire = value,
+            Keycode::P => self.pause = value,
+            Keycode::Q => self.quit = value,
+            Keycode::Escape => self.quit = value,
+            _ => (),
+        }
+    }
+    // Face button A thrusts, X fires, and Start pauses, mirroring the
+    // keyboard's Up/Space/P so gameplay code only ever reads `Keys`.
+    fn set_button(&mut self, button: Button, value: KeyStatus) {
+        match button {
+            Button::A => self.thrust = value,
+            Button::X => self.fire = value,
+            Button::Start => self.pause = value,
+            Button::Back => self.quit = value,
+            _ => (),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -184,31 +352,47 @@ impl Momentum {
     fn get_pos(&self) -> &V2 {
         &self.pos
     }
+    fn get_vel(&self) -> &V2 {
+        &self.vel
+    }
+    // Squared distance to `other`, accounting for toroidal wrap: on each
+    // axis the short way round the screen edge may be shorter than the
+    // direct way.
+    fn wrapped_dist_sq(&self, other: &Momentum) -> f64 {
+        let dx = Momentum::wrapped_axis_dist(self.pos.0, other.pos.0, self.bounds.0);
+        let dy = Momentum::wrapped_axis_dist(self.pos.1, other.pos.1, self.bounds.1);
+        dx * dx + dy * dy
+    }
+    fn wrapped_axis_dist(a: f64, b: f64, bound: f64) -> f64 {
+        let d = (a - b).abs();
+        d.min(bound - d)
+    }
 }
 
 struct Ship<'a> {
     angle: f64,
     momentum: Momentum,
     thrust: bool,
+    accel: f64,
+    angular_accel: f64,
     thrust_texture: Texture<'a>,
     inert_texture: Texture<'a>,
 }
 impl<'a> Ship<'a> {
     // pizxels
     const TEXTURE_SIZE: u32 = 32;
-    // pixels per second per second.
-    const ACCEL: f64 = 100.0;
-    // radians per seocond
-    const ANGULAR_ACCEL: f64 = 4.0;
 
     fn new(
         canvas: &mut Canvas<Window>,
         texture_creator: &'a TextureCreator<WindowContext>,
+        config: &Config,
     ) -> Self {
         Ship {
             angle: 0.0,
-            momentum: Momentum::new(V2(100.0, 100.0), V2(0.0, 0.0), V2(800.0, 600.0)),
+            momentum: Momentum::new(V2(100.0, 100.0), V2(0.0, 0.0), config.window.bounds()),
             thrust: false,
+            accel: config.ship.accel,
+            angular_accel: config.ship.angular_accel,
             thrust_texture: Ship::draw_thrust_texture(canvas, texture_creator),
             inert_texture: Ship::draw_inert_texture(canvas, texture_creator),
         }
@@ -218,22 +402,22 @@ impl<'a> Ship<'a> {
         self.thrust = thrust;
         let dt = f64_duration(duration);
         if left {
-            self.angle -= Ship::ANGULAR_ACCEL * dt;
+            self.angle -= self.angular_accel * dt;
         }
         if right {
-            self.angle += Ship::ANGULAR_ACCEL * dt;
+            self.angle += self.angular_accel * dt;
         }
         let accel = if thrust {
-            V2(
-                self.angle.cos() * Ship::ACCEL,
-                self.angle.sin() * Ship::ACCEL,
-            )
+            V2(self.angle.cos() * self.accel, self.angle.sin() * self.accel)
         } else {
             V2::ZERO
         };
         self.momentum.apply_acceleration(duration, &accel);
     }
 
+    // pixels, for circle-circle collision against asteroids.
+    const RADIUS: f64 = (Ship::TEXTURE_SIZE / 2) as f64;
+
     fn draw(&self, canvas: &mut Canvas<Window>) {
         let centre: Point = self.momentum.get_pos().clone().into();
         let bounds = Rect::from_center(centre, 32, 32);
@@ -304,35 +488,75 @@ impl<'a> Ship<'a> {
     }
 }
 
-struct Asteroid<'a> {
+struct Bullet {
     momentum: Momentum,
-    radius: f64,
-    texture:<'a>,
+    time_left: f64,
 }
-impl Asteroid<'a> {
-    const TEXTURE_SIZE: u32 = 32;
-    const INITIAL_RADIUS: f64 = 32.0;
-    const MIN_RADIUS: f64 = 7.0;
-    const VELOCITY_CHANGE: f64 = 5000.0;
-    fn new (momentum: Momentum, radius: f64) -> Self{
-        Asteroid {
+impl Bullet {
+    // pixels
+    const RADIUS: i16 = 2;
+
+    fn fire(ship: &Ship, config: &Config) -> Self {
+        let direction = V2(ship.angle.cos(), ship.angle.sin());
+        let velocity = ship.momentum.get_vel().clone() + direction * config.bullet.muzzle_speed;
+        let momentum = Momentum::new(
+            ship.momentum.get_pos().clone(),
+            velocity,
+            ship.momentum.bounds.clone(),
+        );
+        Bullet {
             momentum: momentum,
-            radius: radius,
+            time_left: config.bullet.lifetime,
         }
     }
-    fn new_big_asteroid (momentum: Momentum) -> Self{
+
+    fn step(&mut self, duration: &Duration) {
+        self.momentum.no_acceleration(duration);
+        self.time_left -= f64_duration(duration);
+    }
+
+    fn is_alive(&self) -> bool {
+        self.time_left > 0.0
+    }
+
+    fn draw(&self, canvas: &mut Canvas<Window>) {
+        let centre: Point = self.momentum.get_pos().clone().into();
+        canvas
+            .filled_circle(centre.x as i16, centre.y as i16, Bullet::RADIUS, WHITE)
+            .unwrap();
+    }
+}
+
+struct Asteroid {
+    momentum: Momentum,
+    radius: f64,
+    min_radius: f64,
+    velocity_change: f64,
+}
+impl Asteroid {
+    fn new(momentum: Momentum, radius: f64, min_radius: f64, velocity_change: f64) -> Self {
         Asteroid {
             momentum: momentum,
-            radius: Asteroid::INITIAL_RADIUS,
+            radius: radius,
+            min_radius: min_radius,
+            velocity_change: velocity_change,
         }
     }
-    fn split (self) -> Option<(Self, Self)> {
+    fn new_big_asteroid(momentum: Momentum, config: &Config) -> Self {
+        Asteroid::new(
+            momentum,
+            config.asteroid.initial_radius,
+            config.asteroid.min_radius,
+            config.asteroid.velocity_change,
+        )
+    }
+    fn split(self) -> Option<(Self, Self)> {
         let new_radius = self.radius / 2.0;
-        if new_radius < Asteroid::MIN_RADIUS {
+        if new_radius < self.min_radius {
             return None;
         }
 
-        let dv = Asteroid::VELOCITY_CHANGE / new_radius;
+        let dv = self.velocity_change / new_radius;
 
         let mut rng = rand::thread_rng();
         let x1 = rng.gen_range(-dv, dv);
@@ -345,49 +569,386 @@ impl Asteroid<'a> {
         m1.apply_impulse(&V2(x1, y1));
         m2.apply_impulse(&V2(x2, y2));
 
-        Some((Asteroid::new(m1, new_radius), Asteroid::new(m2, new_radius)))
+        Some((
+            Asteroid::new(m1, new_radius, self.min_radius, self.velocity_change),
+            Asteroid::new(m2, new_radius, self.min_radius, self.velocity_change),
+        ))
     }
-    fn step (&mut self, duration: &Duration) {
+    fn step(&mut self, duration: &Duration) {
         self.momentum.no_acceleration(duration);
     }
 
-    fn draw_texture (
+    fn draw(&self, canvas: &mut Canvas<Window>) {
+        let centre: Point = self.momentum.get_pos().clone().into();
+        canvas
+            .circle(centre.x as i16, centre.y as i16, self.radius as i16, WHITE)
+            .unwrap();
+    }
+}
+
+struct Particle {
+    momentum: Momentum,
+    time_left: f64,
+    max_life: f64,
+    color: Color,
+    size: f64,
+}
+impl Particle {
+    fn step(&mut self, duration: &Duration) {
+        self.momentum.no_acceleration(duration);
+        self.time_left -= f64_duration(duration);
+    }
+
+    fn is_alive(&self) -> bool {
+        self.time_left > 0.0
+    }
+
+    fn draw(&self, canvas: &mut Canvas<Window>) {
+        let life_fraction = (self.time_left / self.max_life).max(0.0);
+        let centre: Point = self.momentum.get_pos().clone().into();
+        let mut color = self.color;
+        color.a = (color.a as f64 * life_fraction) as u8;
+        let radius = ((self.size * life_fraction) as i16).max(1);
+        canvas
+            .filled_circle(centre.x as i16, centre.y as i16, radius, color)
+            .unwrap();
+    }
+}
+
+// Pool of short-lived particles for the ship's thrust trail and asteroid
+// explosions, updated and pruned every tick the same way `Bullet`s are.
+struct Particles(Vec<Particle>);
+impl Particles {
+    const THRUST_LIFETIME: f64 = 0.4;
+    // pixels per second, behind the ship's own velocity.
+    const THRUST_SPEED: f64 = 60.0;
+    // radians either side of dead astern.
+    const THRUST_CONE: f64 = 0.4;
+    const THRUST_SIZE: f64 = 3.0;
+    const THRUST_COLOR: Color = Color {
+        r: 0xff,
+        g: 0x80,
+        b: 0x00,
+        a: 0xff,
+    };
+
+    const BURST_LIFETIME: f64 = 0.6;
+    const BURST_SPEED_PER_RADIUS: f64 = 4.0;
+    const BURST_PARTICLES_PER_RADIUS: f64 = 0.5;
+    const BURST_SIZE: f64 = 3.0;
+
+    fn new() -> Self {
+        Particles(Vec::new())
+    }
+
+    fn step(&mut self, duration: &Duration) {
+        self.0.iter_mut().for_each(|particle| particle.step(duration));
+        self.0.retain(Particle::is_alive);
+    }
+
+    fn draw(&self, canvas: &mut Canvas<Window>) {
+        self.0.iter().for_each(|particle| particle.draw(canvas));
+    }
+
+    fn emit_thrust(&mut self, ship: &Ship) {
+        let mut rng = rand::thread_rng();
+        let cone = Uniform::new(-Particles::THRUST_CONE, Particles::THRUST_CONE);
+        let tail_angle = ship.angle + core::f64::consts::PI + rng.sample(cone);
+        let velocity = ship.momentum.get_vel().clone()
+            + V2(tail_angle.cos(), tail_angle.sin()) * Particles::THRUST_SPEED;
+        let momentum = Momentum::new(
+            ship.momentum.get_pos().clone(),
+            velocity,
+            ship.momentum.bounds.clone(),
+        );
+        self.0.push(Particle {
+            momentum: momentum,
+            time_left: Particles::THRUST_LIFETIME,
+            max_life: Particles::THRUST_LIFETIME,
+            color: Particles::THRUST_COLOR,
+            size: Particles::THRUST_SIZE,
+        });
+    }
+
+    fn emit_burst(&mut self, momentum: &Momentum, radius: f64, color: Color) {
+        let mut rng = rand::thread_rng();
+        let count = (radius * Particles::BURST_PARTICLES_PER_RADIUS) as u32;
+        let speed = Uniform::new(0.0, radius * Particles::BURST_SPEED_PER_RADIUS);
+        let angle_dist = Uniform::new(0.0, core::f64::consts::PI * 2.0);
+        for _ in 0..count {
+            let angle = rng.sample(angle_dist);
+            let velocity =
+                momentum.get_vel().clone() + V2(angle.cos(), angle.sin()) * rng.sample(speed);
+            let particle_momentum =
+                Momentum::new(momentum.get_pos().clone(), velocity, momentum.bounds.clone());
+            self.0.push(Particle {
+                momentum: particle_momentum,
+                time_left: Particles::BURST_LIFETIME,
+                max_life: Particles::BURST_LIFETIME,
+                color: color,
+                size: Particles::BURST_SIZE,
+            });
+        }
+    }
+}
+
+// Fixed timestep for the simulation, independent of display refresh rate.
+const UPDATE_DT: Duration = Duration::from_nanos(NANOS_PER_SEC as u64 / 30);
+// Maximum number of catch-up updates to run in a single frame, so a long
+// stall (e.g. the window being dragged) doesn't trigger a spiral of death.
+const UPDATE_MAX_SKIP: u32 = 10;
+
+// Lifecycle state of the app, separate from the physics simulation: what's
+// on screen and how input is interpreted changes across menu / playing /
+// paused / game-over, but each state is still driven by the same fixed
+// timestep from `main`.
+trait AppState<'a> {
+    fn update(&mut self, dt: &Duration, keys: &Keys);
+    fn draw(&mut self, canvas: &mut Canvas<Window>);
+    // Consumes the state and returns the state to continue with: `self`
+    // unchanged, or a new boxed state when a transition was requested.
+    fn next(
+        self: Box<Self>,
+        keys: &Keys,
         canvas: &mut Canvas<Window>,
         texture_creator: &'a TextureCreator<WindowContext>,
-    ) -> Texture<'a> {
-        let mut texture = texture_creator
-            .create_texture_target(None, Asteroid::TEXTURE_SIZE, Asteroid::TEXTURE_SIZE)
-            .expect("Could not create asteroid texture");
-        
-        canvas
-            .with_texture_canvas(&mut texture, |texture_canvas| {
-                texture_canvas.set_draw_color(BLACK);
-                texture_canvas.clear();
-                texture_canvas
-                    .polygon(&[7, 16, 25, 16], &[32, 0, 32, 25], WHITE)
-                    .unwrap();
+        config: &Config,
+    ) -> Box<dyn AppState<'a> + 'a>;
+}
+
+struct MenuState;
+impl MenuState {
+    fn new() -> Self {
+        MenuState
+    }
+}
+impl<'a> AppState<'a> for MenuState {
+    fn update(&mut self, _dt: &Duration, _keys: &Keys) {}
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) {
+        canvas.set_draw_color(BLACK);
+        canvas.clear();
+    }
+
+    fn next(
+        self: Box<Self>,
+        keys: &Keys,
+        canvas: &mut Canvas<Window>,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        config: &Config,
+    ) -> Box<dyn AppState<'a> + 'a> {
+        if keys.fire == KeyStatus::Down {
+            Box::new(PlayingState::new(canvas, texture_creator, config))
+        } else {
+            self
+        }
+    }
+}
+
+struct PlayingState<'a> {
+    ship: Ship<'a>,
+    bullets: Vec<Bullet>,
+    asteroids: Vec<Asteroid>,
+    particles: Particles,
+    game_over: bool,
+    config: Config,
+}
+impl<'a> PlayingState<'a> {
+    fn new(
+        canvas: &mut Canvas<Window>,
+        texture_creator: &'a TextureCreator<WindowContext>,
+        config: &Config,
+    ) -> Self {
+        let bounds = config.window.bounds();
+        let asteroids = (0..4)
+            .map(|i| {
+                let angle = i as f64 * core::f64::consts::PI / 2.0;
+                let position = V2(400.0 + angle.cos() * 300.0, 300.0 + angle.sin() * 300.0);
+                let velocity = V2(angle.sin() * -40.0, angle.cos() * 40.0);
+                Asteroid::new_big_asteroid(Momentum::new(position, velocity, bounds.clone()), config)
             })
-            .expect("Could not draw asteroid texture");
+            .collect();
+        PlayingState {
+            ship: Ship::new(canvas, texture_creator, config),
+            bullets: Vec::new(),
+            asteroids: asteroids,
+            particles: Particles::new(),
+            game_over: false,
+            config: config.clone(),
+        }
+    }
+}
+impl<'a> AppState<'a> for PlayingState<'a> {
+    fn update(&mut self, dt: &Duration, keys: &Keys) {
+        self.ship
+            .step(dt, keys.thrust.down(), keys.left_down(), keys.right_down());
+        if keys.fire == KeyStatus::Down {
+            self.bullets.push(Bullet::fire(&self.ship, &self.config));
+        }
+        if keys.thrust.down() {
+            self.particles.emit_thrust(&self.ship);
+        }
+
+        self.bullets.iter_mut().for_each(|bullet| bullet.step(dt));
+        self.bullets.retain(Bullet::is_alive);
+
+        self.asteroids.iter_mut().for_each(|asteroid| asteroid.step(dt));
+        self.particles.step(dt);
+
+        // Bullet-asteroid collisions: test every bullet against every
+        // asteroid by squared distance (no sqrt needed), replacing a hit
+        // asteroid with its split children, or removing it once `split`
+        // says it's too small to split further.
+        let mut spawned = Vec::new();
+        let mut i = 0;
+        while i < self.asteroids.len() {
+            let hit = self.bullets.iter().position(|bullet| {
+                bullet.momentum.wrapped_dist_sq(&self.asteroids[i].momentum)
+                    < (self.asteroids[i].radius + Bullet::RADIUS as f64).powi(2)
+            });
+            match hit {
+                Some(bullet_index) => {
+                    self.bullets.remove(bullet_index);
+                    let asteroid = self.asteroids.remove(i);
+                    self.particles
+                        .emit_burst(&asteroid.momentum, asteroid.radius, WHITE);
+                    if let Some((a, b)) = asteroid.split() {
+                        spawned.push(a);
+                        spawned.push(b);
+                    }
+                }
+                None => i += 1,
+            }
+        }
+        self.asteroids.extend(spawned);
+
+        if self.asteroids.iter().any(|asteroid| {
+            self.ship.momentum.wrapped_dist_sq(&asteroid.momentum)
+                < (asteroid.radius + Ship::RADIUS).powi(2)
+        }) {
+            self.game_over = true;
+        }
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) {
+        canvas.set_draw_color(BLACK);
+        canvas.clear();
+
+        self.ship.draw(canvas);
+        self.bullets.iter().for_each(|bullet| bullet.draw(canvas));
+        self.asteroids.iter().for_each(|asteroid| asteroid.draw(canvas));
+        self.particles.draw(canvas);
+    }
+
+    fn next(
+        self: Box<Self>,
+        keys: &Keys,
+        _canvas: &mut Canvas<Window>,
+        _texture_creator: &'a TextureCreator<WindowContext>,
+        _config: &Config,
+    ) -> Box<dyn AppState<'a> + 'a> {
+        if self.game_over {
+            Box::new(GameOverState::new())
+        } else if keys.pause == KeyStatus::Down {
+            Box::new(PausedState::new(self))
+        } else {
+            self
+        }
+    }
+}
+
+// Freezes the simulation while still drawing the frame the game was
+// paused on, and hands the same `PlayingState` back unchanged on unpause.
+struct PausedState<'a> {
+    inner: Box<PlayingState<'a>>,
+}
+impl<'a> PausedState<'a> {
+    fn new(inner: Box<PlayingState<'a>>) -> Self {
+        PausedState { inner: inner }
+    }
+}
+impl<'a> AppState<'a> for PausedState<'a> {
+    fn update(&mut self, _dt: &Duration, _keys: &Keys) {}
 
+    fn draw(&mut self, canvas: &mut Canvas<Window>) {
+        self.inner.draw(canvas);
+    }
+
+    fn next(
+        self: Box<Self>,
+        keys: &Keys,
+        _canvas: &mut Canvas<Window>,
+        _texture_creator: &'a TextureCreator<WindowContext>,
+        _config: &Config,
+    ) -> Box<dyn AppState<'a> + 'a> {
+        if keys.pause == KeyStatus::Down {
+            self.inner
+        } else {
+            self
+        }
+    }
+}
+
+struct GameOverState;
+impl GameOverState {
+    fn new() -> Self {
+        GameOverState
+    }
+}
+impl<'a> AppState<'a> for GameOverState {
+    fn update(&mut self, _dt: &Duration, _keys: &Keys) {}
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) {
+        canvas.set_draw_color(BLACK);
+        canvas.clear();
+    }
+
+    fn next(
+        self: Box<Self>,
+        keys: &Keys,
+        _canvas: &mut Canvas<Window>,
+        _texture_creator: &'a TextureCreator<WindowContext>,
+        _config: &Config,
+    ) -> Box<dyn AppState<'a> + 'a> {
+        if keys.fire == KeyStatus::Down {
+            Box::new(MenuState::new())
+        } else {
+            self
+        }
     }
 }
 
 pub fn main() {
+    let config = Config::load();
+
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video().unwrap();
     let window = video_subsystem
-        .window("Asteroids", 800, 600)
+        .window(
+            "Asteroids",
+            config.window.width as u32,
+            config.window.height as u32,
+        )
         .position_centered()
         .build()
         .unwrap();
     let mut canvas = window.into_canvas().build().unwrap();
     let texture_creator = canvas.texture_creator();
 
+    // Open every controller already plugged in so its button/axis events
+    // show up in the event pump; the handles must stay alive for that.
+    let game_controller_subsystem = sdl_context.game_controller().unwrap();
+    let _controllers: Vec<GameController> = (0..game_controller_subsystem.num_joysticks().unwrap_or(0))
+        .filter(|&id| game_controller_subsystem.is_game_controller(id))
+        .filter_map(|id| game_controller_subsystem.open(id).ok())
+        .collect();
+
     let mut keys = Keys::new();
     let mut time = Instant::now();
+    let mut accumulator = Duration::new(0, 0);
 
     let mut event_pump = sdl_context.event_pump().unwrap();
-    let mut ship = Ship::new(&mut canvas, &texture_creator);
+    let mut state: Box<dyn AppState<'_> + '_> = Box::new(MenuState::new());
     loop {
         keys.with_events(&mut event_pump);
         if let Keys {
@@ -398,14 +959,26 @@ pub fn main() {
             break;
         }
 
-        let dt = time.elapsed();
-        time = Instant::now();
-        canvas.set_draw_color(BLACK);
-        canvas.clear();
+        let now = Instant::now();
+        accumulator += now - time;
+        time = now;
 
-        ship.step(&dt, keys.thrust.down(), keys.left.down(), keys.right.down());
-        ship.draw(&mut canvas);
+        let mut updates = 0;
+        while accumulator >= UPDATE_DT && updates < UPDATE_MAX_SKIP {
+            state.update(&UPDATE_DT, &keys);
+            state = state.next(&keys, &mut canvas, &texture_creator, &config);
+            keys.step();
+
+            accumulator -= UPDATE_DT;
+            updates += 1;
+        }
+        // A stall longer than we can catch up on: drop the backlog rather
+        // than burning every future frame trying to make up the difference.
+        if updates == UPDATE_MAX_SKIP {
+            accumulator = Duration::new(0, 0);
+        }
 
+        state.draw(&mut canvas);
         canvas.present();
 
         std::thread::sleep(std::time::Duration::from_millis(1000 / 60));